@@ -1,8 +1,12 @@
+use crate::chunk_codec::{Decode, Encode};
 use crate::chunk_type::{ChunkType, ChunkTypeError};
-use crc::crc32::checksum_ieee;
+use crc::crc32::{self, checksum_ieee, Digest};
+use crc::Hasher32;
 use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
 
 #[derive(Debug)]
 pub enum ChunkError {
@@ -11,6 +15,10 @@ pub enum ChunkError {
     InvalidCRC(u32, u32),
     LengthMismatch(u32, u32),
     ChunkTooShort,
+    UnexpectedEof,
+    Io(io::Error),
+    TypeMismatch(String, String),
+    PayloadError(String),
 }
 
 impl fmt::Display for ChunkError {
@@ -31,6 +39,16 @@ impl fmt::Display for ChunkError {
                 found, expected
             ),
             ChunkError::ChunkTooShort => write!(f, "Chunk must be at least 12 bytes long."),
+            ChunkError::UnexpectedEof => {
+                write!(f, "Reader ended partway through a chunk.")
+            }
+            ChunkError::Io(err) => write!(f, "I/O error while reading chunk: {}", err),
+            ChunkError::TypeMismatch(found, expected) => write!(
+                f,
+                "Cannot decode a {} chunk as {}",
+                found, expected
+            ),
+            ChunkError::PayloadError(msg) => write!(f, "Invalid chunk payload: {}", msg),
         }
     }
 }
@@ -40,6 +58,7 @@ impl Error for ChunkError {
         match self {
             ChunkError::UTF8Error(err) => Some(err),
             ChunkError::ChunkTypeError(err) => Some(err),
+            ChunkError::Io(err) => Some(err),
             _ => None,
         }
     }
@@ -156,6 +175,233 @@ impl Chunk {
             .collect();
         return bytes;
     }
+
+    /// Interprets this chunk's `data` as `T`, failing if `chunk_type` doesn't
+    /// match `T::CHUNK_TYPE`.
+    pub fn decode_payload<T: Decode>(&self) -> Result<T, ChunkError> {
+        let found = self.chunk_type.to_string();
+        if found != T::CHUNK_TYPE {
+            return Err(ChunkError::TypeMismatch(found, T::CHUNK_TYPE.to_string()));
+        }
+        T::decode(&self.data)
+    }
+
+    /// Builds a chunk of type `T::CHUNK_TYPE` whose `data` is `value` encoded.
+    pub fn from_payload<T: Encode>(value: &T) -> Result<Self, std::num::TryFromIntError> {
+        let chunk_type =
+            ChunkType::from_str(T::CHUNK_TYPE).expect("T::CHUNK_TYPE is a valid chunk type");
+        Chunk::new(chunk_type, value.encode())
+    }
+
+    /// Writes this chunk's length, type, data, and CRC directly to `w`,
+    /// without allocating an intermediate buffer the way `as_bytes` does.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.length.to_be_bytes())?;
+        w.write_all(&self.chunk_type.bytes)?;
+        w.write_all(&self.data)?;
+        w.write_all(&self.crc.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// Splits `data` into consecutive `chunk_type` chunks of at most
+    /// `max_chunk_size` bytes each, so a payload larger than a single chunk
+    /// comfortably holds can still be stashed as several ordinary-looking
+    /// chunks of the same type. A `max_chunk_size` of 0 is treated as 1, and
+    /// empty `data` yields a single empty chunk.
+    pub fn split_message(
+        chunk_type: ChunkType,
+        data: &[u8],
+        max_chunk_size: usize,
+    ) -> Result<Vec<Chunk>, std::num::TryFromIntError> {
+        if data.is_empty() {
+            return Ok(vec![Chunk::new(chunk_type, Vec::new())?]);
+        }
+        data.chunks(max_chunk_size.max(1))
+            .map(|piece| Chunk::new(chunk_type, piece.to_vec()))
+            .collect()
+    }
+
+    /// Concatenates the `data` of a sequence of chunks, in order, back into
+    /// the original message split across them by `split_message`.
+    pub fn reassemble<'a>(chunks: impl Iterator<Item = &'a Chunk>) -> Vec<u8> {
+        chunks.flat_map(|chunk| chunk.data.iter().copied()).collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ReaderState {
+    Length,
+    Type,
+    Data,
+    Crc,
+    /// Draining a malformed chunk's remaining data+CRC bytes after its type
+    /// was rejected, so the stream stays in sync for the next chunk. Holds
+    /// no payload itself; the error to report once draining finishes is
+    /// kept in `pending_error`.
+    Draining,
+}
+
+/// Pulls `Chunk`s one at a time out of any `Read`, without buffering more
+/// than a single chunk's worth of data at once.
+///
+/// Internally this is a small state machine (`Length` -> `Type` -> `Data` ->
+/// `Crc`) that accumulates bytes into a scratch buffer. If the underlying
+/// reader returns a short read (or an interruptible error), the target byte
+/// count and scratch buffer are preserved so the next call to `next_chunk`
+/// picks up exactly where the last one left off instead of restarting the
+/// chunk.
+pub struct ChunkReader<R: Read> {
+    reader: R,
+    state: ReaderState,
+    scratch: Vec<u8>,
+    target: usize,
+    length: u32,
+    chunk_type: Option<ChunkType>,
+    data: Vec<u8>,
+    digest: Digest,
+    pending_error: Option<ChunkTypeError>,
+}
+
+impl<R: Read> ChunkReader<R> {
+    pub fn new(reader: R) -> Self {
+        ChunkReader {
+            reader,
+            state: ReaderState::Length,
+            scratch: Vec::new(),
+            target: 4,
+            length: 0,
+            chunk_type: None,
+            data: Vec::new(),
+            digest: Digest::new(crc32::IEEE),
+            pending_error: None,
+        }
+    }
+
+    /// Reads the next chunk, or `Ok(None)` if the reader is at a clean EOF
+    /// between chunks.
+    pub fn next_chunk(&mut self) -> Result<Option<Chunk>, ChunkError> {
+        loop {
+            match self.state {
+                ReaderState::Length => {
+                    if !self.fill_scratch()? {
+                        if self.scratch.is_empty() {
+                            return Ok(None);
+                        }
+                        return Err(ChunkError::UnexpectedEof);
+                    }
+                    let mut length_bytes = [0u8; 4];
+                    length_bytes.copy_from_slice(&self.scratch);
+                    self.length = u32::from_be_bytes(length_bytes);
+                    self.scratch.clear();
+                    self.target = 4;
+                    self.state = ReaderState::Type;
+                }
+                ReaderState::Type => {
+                    if !self.fill_scratch()? {
+                        return Err(ChunkError::UnexpectedEof);
+                    }
+                    let mut ct_bytes = [0u8; 4];
+                    ct_bytes.copy_from_slice(&self.scratch);
+                    let chunk_type = match ChunkType::try_from(ct_bytes) {
+                        Ok(chunk_type) => chunk_type,
+                        Err(err) => {
+                            // The length field was already read correctly, so
+                            // the reader still has this chunk's data and CRC
+                            // ahead of it even though its type is malformed.
+                            // Drain them in the `Draining` state so the next
+                            // call to `next_chunk` resyncs on the following
+                            // chunk's length field instead of re-reading
+                            // these same stale bytes forever.
+                            self.scratch.clear();
+                            self.target = match (self.length as usize).checked_add(4) {
+                                Some(target) => target,
+                                // The declared length is too large to drain
+                                // on this platform; give up resyncing and
+                                // just report the original error.
+                                None => {
+                                    self.target = 4;
+                                    self.state = ReaderState::Length;
+                                    return Err(ChunkError::from(err));
+                                }
+                            };
+                            self.pending_error = Some(err);
+                            self.state = ReaderState::Draining;
+                            continue;
+                        }
+                    };
+                    let mut digest = Digest::new(crc32::IEEE);
+                    digest.write(&ct_bytes);
+                    self.digest = digest;
+                    self.chunk_type = Some(chunk_type);
+                    self.scratch.clear();
+                    self.target = self.length as usize;
+                    self.state = ReaderState::Data;
+                }
+                ReaderState::Data => {
+                    if !self.fill_scratch()? {
+                        return Err(ChunkError::UnexpectedEof);
+                    }
+                    self.digest.write(&self.scratch);
+                    self.data = std::mem::take(&mut self.scratch);
+                    self.target = 4;
+                    self.state = ReaderState::Crc;
+                }
+                ReaderState::Crc => {
+                    if !self.fill_scratch()? {
+                        return Err(ChunkError::UnexpectedEof);
+                    }
+                    let mut crc_bytes = [0u8; 4];
+                    crc_bytes.copy_from_slice(&self.scratch);
+                    let crc = u32::from_be_bytes(crc_bytes);
+                    let computed_crc = self.digest.sum32();
+                    self.scratch.clear();
+                    self.target = 4;
+                    self.state = ReaderState::Length;
+                    if computed_crc != crc {
+                        return Err(ChunkError::InvalidCRC(computed_crc, crc));
+                    }
+                    return Ok(Some(Chunk {
+                        length: self.length,
+                        chunk_type: self.chunk_type.take().unwrap(),
+                        data: std::mem::take(&mut self.data),
+                        crc,
+                    }));
+                }
+                ReaderState::Draining => {
+                    // A genuine I/O error here propagates via `?` without
+                    // touching `state`/`scratch`/`target`, so the next call
+                    // resumes the drain exactly where this one left off
+                    // instead of silently reporting a fully-resynced reader.
+                    // A clean EOF just means there was nothing left to
+                    // drain; either way the error being surfaced is the
+                    // chunk type rejection, not the drain itself.
+                    self.fill_scratch()?;
+                    self.scratch.clear();
+                    self.target = 4;
+                    self.state = ReaderState::Length;
+                    return Err(ChunkError::from(self.pending_error.take().unwrap()));
+                }
+            }
+        }
+    }
+
+    /// Reads into `scratch` until it holds `target` bytes. Returns `Ok(true)`
+    /// once the target is met, or `Ok(false)` on a clean EOF (a `read` that
+    /// returned zero bytes) before the target was reached.
+    fn fill_scratch(&mut self) -> Result<bool, ChunkError> {
+        let mut buf = [0u8; 4096];
+        while self.scratch.len() < self.target {
+            let remaining = self.target - self.scratch.len();
+            let want = remaining.min(buf.len());
+            match self.reader.read(&mut buf[..want]) {
+                Ok(0) => return Ok(false),
+                Ok(n) => self.scratch.extend_from_slice(&buf[..n]),
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(ChunkError::Io(err)),
+            }
+        }
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -245,4 +491,226 @@ mod tests {
 
         assert!(chunk.is_err());
     }
+
+    #[test]
+    fn test_chunk_reader_reads_one_chunk_at_a_time() {
+        let chunk = testing_chunk();
+        let bytes = chunk.as_bytes();
+        let mut reader = ChunkReader::new(bytes.as_slice());
+
+        let first = reader.next_chunk().unwrap().unwrap();
+        assert_eq!(first.chunk_type().to_string(), chunk.chunk_type().to_string());
+        assert_eq!(first.data(), chunk.data());
+
+        assert!(reader.next_chunk().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_chunk_reader_reads_multiple_chunks_in_order() {
+        let first = testing_chunk();
+        let second_type = ChunkType::from_str("TeSt").unwrap();
+        let second = Chunk::new(second_type, b"more data".to_vec()).unwrap();
+
+        let mut bytes = first.as_bytes();
+        bytes.extend_from_slice(&second.as_bytes());
+        let mut reader = ChunkReader::new(bytes.as_slice());
+
+        let read_first = reader.next_chunk().unwrap().unwrap();
+        let read_second = reader.next_chunk().unwrap().unwrap();
+
+        assert_eq!(read_first.data(), first.data());
+        assert_eq!(read_second.data(), second.data());
+        assert!(reader.next_chunk().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_chunk_reader_tolerates_short_reads() {
+        struct OneByteAtATime<'a>(&'a [u8]);
+        impl<'a> Read for OneByteAtATime<'a> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                if self.0.is_empty() || buf.is_empty() {
+                    return Ok(0);
+                }
+                buf[0] = self.0[0];
+                self.0 = &self.0[1..];
+                Ok(1)
+            }
+        }
+
+        let chunk = testing_chunk();
+        let bytes = chunk.as_bytes();
+        let mut reader = ChunkReader::new(OneByteAtATime(bytes.as_slice()));
+
+        let read = reader.next_chunk().unwrap().unwrap();
+        assert_eq!(read.data(), chunk.data());
+    }
+
+    #[test]
+    fn test_decode_payload_round_trips_through_a_typed_struct() {
+        use crate::chunk_codec::Ihdr;
+
+        let ihdr = Ihdr {
+            width: 64,
+            height: 32,
+            bit_depth: 8,
+            color_type: 6,
+            compression_method: 0,
+            filter_method: 0,
+            interlace_method: 0,
+        };
+        let chunk = Chunk::from_payload(&ihdr).unwrap();
+        let decoded: Ihdr = chunk.decode_payload().unwrap();
+        assert_eq!(decoded, ihdr);
+    }
+
+    #[test]
+    fn test_decode_payload_rejects_mismatched_chunk_type() {
+        use crate::chunk_codec::Ihdr;
+
+        let chunk = testing_chunk();
+        assert!(chunk.decode_payload::<Ihdr>().is_err());
+    }
+
+    #[test]
+    fn test_split_message_bounds_each_chunk_and_reassembles() {
+        let chunk_type = ChunkType::from_str("RuSt").unwrap();
+        let message: Vec<u8> = (0..100).collect();
+
+        let chunks = Chunk::split_message(chunk_type, &message, 30).unwrap();
+
+        assert_eq!(chunks.len(), 4);
+        assert!(chunks.iter().all(|chunk| chunk.data().len() <= 30));
+        assert_eq!(Chunk::reassemble(chunks.iter()), message);
+    }
+
+    #[test]
+    fn test_split_message_of_empty_data_yields_one_empty_chunk() {
+        let chunk_type = ChunkType::from_str("RuSt").unwrap();
+
+        let chunks = Chunk::split_message(chunk_type, &[], 30).unwrap();
+
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].data().is_empty());
+    }
+
+    #[test]
+    fn test_chunk_reader_detects_invalid_crc() {
+        let chunk = testing_chunk();
+        let mut bytes = chunk.as_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        let mut reader = ChunkReader::new(bytes.as_slice());
+
+        assert!(reader.next_chunk().is_err());
+    }
+
+    #[test]
+    fn test_chunk_reader_recovers_after_an_invalid_chunk_type() {
+        let bad_type = [0x31u8, b'u', b'S', b't']; // '1' is not an ASCII letter
+        let bad_data = b"bogus".to_vec();
+        let bad_crc: u32 = 0; // never checked; the type is rejected first
+        let bad_chunk_bytes: Vec<u8> = (bad_data.len() as u32)
+            .to_be_bytes()
+            .iter()
+            .chain(bad_type.iter())
+            .chain(bad_data.iter())
+            .chain(bad_crc.to_be_bytes().iter())
+            .copied()
+            .collect();
+
+        let good_chunk = testing_chunk();
+        let mut bytes = bad_chunk_bytes;
+        bytes.extend_from_slice(&good_chunk.as_bytes());
+        let mut reader = ChunkReader::new(bytes.as_slice());
+
+        assert!(reader.next_chunk().is_err());
+
+        let recovered = reader.next_chunk().unwrap().unwrap();
+        assert_eq!(recovered.data(), good_chunk.data());
+        assert!(reader.next_chunk().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_chunk_reader_propagates_io_errors_while_draining() {
+        struct FailAfter<'a> {
+            data: &'a [u8],
+            reads_remaining: usize,
+        }
+        impl<'a> Read for FailAfter<'a> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                if self.reads_remaining == 0 {
+                    return Err(io::Error::other("boom"));
+                }
+                if self.data.is_empty() || buf.is_empty() {
+                    return Ok(0);
+                }
+                buf[0] = self.data[0];
+                self.data = &self.data[1..];
+                self.reads_remaining -= 1;
+                Ok(1)
+            }
+        }
+
+        let bad_type = [0x31u8, b'u', b'S', b't']; // '1' is not an ASCII letter
+        let bad_data = b"bogus".to_vec();
+        let bad_crc: u32 = 0;
+        let bytes: Vec<u8> = (bad_data.len() as u32)
+            .to_be_bytes()
+            .iter()
+            .chain(bad_type.iter())
+            .chain(bad_data.iter())
+            .chain(bad_crc.to_be_bytes().iter())
+            .copied()
+            .collect();
+
+        // Let the 8 length+type bytes through so the reader enters the
+        // `Draining` state, then fail on the very next read instead of
+        // reaching a clean EOF.
+        let reader_input = FailAfter {
+            data: bytes.as_slice(),
+            reads_remaining: 8,
+        };
+        let mut reader = ChunkReader::new(reader_input);
+
+        match reader.next_chunk() {
+            Err(ChunkError::Io(_)) => {}
+            other => panic!("expected ChunkError::Io, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_chunk_reader_reports_unexpected_eof_on_a_truncated_stream() {
+        let chunk = testing_chunk();
+        let bytes = chunk.as_bytes();
+
+        // Truncate partway through the length field: a non-empty partial
+        // read followed by a clean EOF, rather than EOF right at a chunk
+        // boundary.
+        let mut reader = ChunkReader::new(&bytes[0..2]);
+        assert!(matches!(
+            reader.next_chunk(),
+            Err(ChunkError::UnexpectedEof)
+        ));
+
+        // Truncate partway through the type field.
+        let mut reader = ChunkReader::new(&bytes[0..6]);
+        assert!(matches!(
+            reader.next_chunk(),
+            Err(ChunkError::UnexpectedEof)
+        ));
+
+        // Truncate partway through the data field.
+        let mut reader = ChunkReader::new(&bytes[0..(bytes.len() - 6)]);
+        assert!(matches!(
+            reader.next_chunk(),
+            Err(ChunkError::UnexpectedEof)
+        ));
+
+        // Truncate partway through the CRC field.
+        let mut reader = ChunkReader::new(&bytes[0..(bytes.len() - 2)]);
+        assert!(matches!(
+            reader.next_chunk(),
+            Err(ChunkError::UnexpectedEof)
+        ));
+    }
 }
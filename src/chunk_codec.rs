@@ -0,0 +1,153 @@
+//! Typed payloads for standard PNG chunk types.
+//!
+//! `Chunk` itself only knows about raw bytes; the `Decode`/`Encode` traits
+//! here let a chunk's `data` be interpreted as (or built from) a concrete
+//! struct for chunk types pngme understands. Chunk types with no entry here
+//! simply stay as raw bytes.
+
+use crate::chunk::ChunkError;
+
+/// Interprets a chunk's raw `data` as a typed payload.
+pub trait Decode: Sized {
+    /// The four-character chunk type this payload decodes, e.g. `"IHDR"`.
+    const CHUNK_TYPE: &'static str;
+
+    fn decode(data: &[u8]) -> Result<Self, ChunkError>;
+}
+
+/// Serializes a typed payload back into a chunk's raw `data`.
+pub trait Encode {
+    /// The four-character chunk type this payload encodes, e.g. `"IHDR"`.
+    const CHUNK_TYPE: &'static str;
+
+    fn encode(&self) -> Vec<u8>;
+}
+
+/// The `IHDR` header chunk: image dimensions and pixel format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ihdr {
+    pub width: u32,
+    pub height: u32,
+    pub bit_depth: u8,
+    pub color_type: u8,
+    pub compression_method: u8,
+    pub filter_method: u8,
+    pub interlace_method: u8,
+}
+
+impl Decode for Ihdr {
+    const CHUNK_TYPE: &'static str = "IHDR";
+
+    fn decode(data: &[u8]) -> Result<Self, ChunkError> {
+        if data.len() != 13 {
+            return Err(ChunkError::PayloadError(format!(
+                "IHDR payload must be 13 bytes, found {}",
+                data.len()
+            )));
+        }
+        let mut width_bytes = [0u8; 4];
+        width_bytes.copy_from_slice(&data[0..4]);
+        let mut height_bytes = [0u8; 4];
+        height_bytes.copy_from_slice(&data[4..8]);
+        Ok(Ihdr {
+            width: u32::from_be_bytes(width_bytes),
+            height: u32::from_be_bytes(height_bytes),
+            bit_depth: data[8],
+            color_type: data[9],
+            compression_method: data[10],
+            filter_method: data[11],
+            interlace_method: data[12],
+        })
+    }
+}
+
+impl Encode for Ihdr {
+    const CHUNK_TYPE: &'static str = "IHDR";
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(13);
+        bytes.extend_from_slice(&self.width.to_be_bytes());
+        bytes.extend_from_slice(&self.height.to_be_bytes());
+        bytes.push(self.bit_depth);
+        bytes.push(self.color_type);
+        bytes.push(self.compression_method);
+        bytes.push(self.filter_method);
+        bytes.push(self.interlace_method);
+        bytes
+    }
+}
+
+/// A `tEXt` chunk: a keyword and a text value separated by a null byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Text {
+    pub keyword: String,
+    pub text: String,
+}
+
+impl Decode for Text {
+    const CHUNK_TYPE: &'static str = "tEXt";
+
+    fn decode(data: &[u8]) -> Result<Self, ChunkError> {
+        let null_index = data.iter().position(|&b| b == 0).ok_or_else(|| {
+            ChunkError::PayloadError("tEXt payload is missing the null separator".to_string())
+        })?;
+        let keyword = String::from_utf8(data[..null_index].to_vec()).map_err(ChunkError::UTF8Error)?;
+        let text =
+            String::from_utf8(data[(null_index + 1)..].to_vec()).map_err(ChunkError::UTF8Error)?;
+        Ok(Text { keyword, text })
+    }
+}
+
+impl Encode for Text {
+    const CHUNK_TYPE: &'static str = "tEXt";
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.keyword.len() + 1 + self.text.len());
+        bytes.extend_from_slice(self.keyword.as_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(self.text.as_bytes());
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ihdr_round_trip() {
+        let ihdr = Ihdr {
+            width: 800,
+            height: 600,
+            bit_depth: 8,
+            color_type: 6,
+            compression_method: 0,
+            filter_method: 0,
+            interlace_method: 0,
+        };
+        let encoded = ihdr.encode();
+        let decoded = Ihdr::decode(&encoded).unwrap();
+        assert_eq!(ihdr, decoded);
+    }
+
+    #[test]
+    fn test_ihdr_decode_rejects_wrong_length() {
+        assert!(Ihdr::decode(&[0u8; 12]).is_err());
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let text = Text {
+            keyword: "Author".to_string(),
+            text: "pngme".to_string(),
+        };
+        let encoded = text.encode();
+        let decoded = Text::decode(&encoded).unwrap();
+        assert_eq!(text, decoded);
+    }
+
+    #[test]
+    fn test_text_decode_rejects_missing_separator() {
+        assert!(Text::decode(b"no separator here").is_err());
+    }
+}
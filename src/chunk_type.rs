@@ -1,6 +1,45 @@
 use std::convert::TryFrom;
 use std::fmt;
 
+const IS_ASCII_LETTER: u8 = 0b0001;
+const IS_UPPER: u8 = 0b0010;
+const IS_LOWER: u8 = 0b0100;
+// PNG's property bits (critical/public/reserved/safe-to-copy) are each bit 5
+// of a chunk type byte, and the spec defines them for that raw byte, not
+// just for ASCII letters. Track it separately from IS_UPPER/IS_LOWER so the
+// property methods below agree with a direct `byte & 32` check for every
+// byte, including ones `ChunkType::bytes` can hold without going through
+// `is_valid`/`TryFrom` (it's a `pub` field).
+const BIT5_SET: u8 = 0b1000;
+
+const fn classify(byte: u8) -> u8 {
+    let letter_flags = if byte.is_ascii_uppercase() {
+        IS_ASCII_LETTER | IS_UPPER
+    } else if byte.is_ascii_lowercase() {
+        IS_ASCII_LETTER | IS_LOWER
+    } else {
+        0
+    };
+    if byte & 32 == 32 {
+        letter_flags | BIT5_SET
+    } else {
+        letter_flags
+    }
+}
+
+/// Bitflags (`IS_ASCII_LETTER`, `IS_UPPER`, `IS_LOWER`, `BIT5_SET`) for every
+/// possible byte value, built once so chunk type validation is a table
+/// lookup rather than a pair of range comparisons per byte.
+const CLASS: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = classify(i as u8);
+        i += 1;
+    }
+    table
+};
+
 #[derive(Debug)]
 pub enum ChunkTypeError {
     InvalidByte(u8),
@@ -35,40 +74,32 @@ impl ChunkType {
     /// Valid chunk types follow these rules:
     /// 1) They must consist of ASCII letters (uppercase or lowercase).
     /// 2) The third character must be uppercase.
-    fn is_valid(&self) -> bool {
-        // Lowercase ASCII
-        let lower = 65u8..=90;
-        // Uppercase ASCII
-        let upper = 97u8..=122;
+    pub fn is_valid(&self) -> bool {
         for byte in self.bytes.iter() {
-            if !(lower.contains(&byte)) && !(upper.contains(&byte)) {
+            if CLASS[*byte as usize] & IS_ASCII_LETTER == 0 {
                 return false;
             }
         }
-        // Third character is uppercase
-        if (self.bytes[2] & 32) == 32 {
-            return false;
-        }
-        return true;
+        self.is_reserved_bit_valid()
     }
 
     /// Returns true if bit 5 of the first byte is 0.
     ///
     /// A critical chunk type is necessary to meaningfully display the contents of the file.
-    fn is_critical(&self) -> bool {
-        return (self.bytes[0] & 32) == 0;
+    pub fn is_critical(&self) -> bool {
+        CLASS[self.bytes[0] as usize] & BIT5_SET == 0
     }
 
-    fn is_public(&self) -> bool {
-        return (self.bytes[1] & 32) == 0;
+    pub fn is_public(&self) -> bool {
+        CLASS[self.bytes[1] as usize] & BIT5_SET == 0
     }
 
-    fn is_reserved_bit_valid(&self) -> bool {
-        return (self.bytes[2] & 32) == 0;
+    pub fn is_reserved_bit_valid(&self) -> bool {
+        CLASS[self.bytes[2] as usize] & BIT5_SET == 0
     }
 
-    fn is_safe_to_copy(&self) -> bool {
-        return (self.bytes[3] & 32) == 32;
+    pub fn is_safe_to_copy(&self) -> bool {
+        CLASS[self.bytes[3] as usize] & BIT5_SET != 0
     }
 }
 
@@ -86,10 +117,8 @@ impl TryFrom<[u8; 4]> for ChunkType {
     type Error = ChunkTypeError;
 
     fn try_from(value: [u8; 4]) -> Result<Self, Self::Error> {
-        let lower = 65u8..=90;
-        let upper = 97u8..=122;
         for byte in value.iter() {
-            if !(lower.contains(&byte)) && !(upper.contains(&byte)) {
+            if CLASS[*byte as usize] & IS_ASCII_LETTER == 0 {
                 return Err(ChunkTypeError::InvalidByte(*byte));
             }
         }
@@ -194,6 +223,19 @@ mod tests {
         assert!(chunk.is_err());
     }
 
+    #[test]
+    pub fn test_property_methods_match_raw_bit_5_outside_ascii_letters() {
+        // `bytes` is a pub field, so a ChunkType can hold bytes that never
+        // passed through `is_valid`/`TryFrom`. The property methods must
+        // still agree with a direct `byte & 32` check in that case.
+        let chunk = ChunkType { bytes: [225, 225, 225, 225] };
+        assert_eq!(225u8 & 32, 32);
+        assert!(!chunk.is_critical());
+        assert!(!chunk.is_public());
+        assert!(!chunk.is_reserved_bit_valid());
+        assert!(chunk.is_safe_to_copy());
+    }
+
     #[test]
     pub fn test_chunk_type_string() {
         let chunk = ChunkType::from_str("RuSt").unwrap();
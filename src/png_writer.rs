@@ -0,0 +1,98 @@
+//! Streams a PNG signature and an ordered sequence of chunks directly to a
+//! writer, mirroring `ChunkReader` on the decode side. Each chunk is pushed
+//! out via `Chunk::write_to`, so writing a file back out allocates no more
+//! than the chunks themselves already hold.
+
+use crate::chunk::Chunk;
+use std::io::{self, Write};
+
+pub const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+pub struct PngWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> PngWriter<W> {
+    pub fn new(writer: W) -> Self {
+        PngWriter { writer }
+    }
+
+    pub fn write_signature(&mut self) -> io::Result<()> {
+        self.writer.write_all(&PNG_SIGNATURE)
+    }
+
+    // No #[bench]/criterion case accompanies this: this crate has no
+    // Cargo.toml in this tree to host a `[[bench]]` target or a `criterion`
+    // dev-dependency, so there's nowhere to run one. The zero-allocation
+    // property itself is structural rather than something a benchmark would
+    // need to detect — `write_to`/`write_chunk` only ever call `write_all`
+    // on bytes the `Chunk` already owns, with no intermediate `Vec` the way
+    // `Chunk::as_bytes` builds one.
+    pub fn write_chunk(&mut self, chunk: &Chunk) -> io::Result<()> {
+        chunk.write_to(&mut self.writer)
+    }
+
+    /// Writes the signature followed by every chunk in `chunks`, in order.
+    pub fn write_chunks<'a, I>(&mut self, chunks: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a Chunk>,
+    {
+        self.write_signature()?;
+        for chunk in chunks {
+            self.write_chunk(chunk)?;
+        }
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk_type::ChunkType;
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    fn test_chunk(chunk_type: &str, data: &[u8]) -> Chunk {
+        let chunk_type_bytes = ChunkType::from_str(chunk_type).unwrap().bytes;
+        let crc = crc::crc32::checksum_ieee(
+            &chunk_type_bytes
+                .iter()
+                .chain(data.iter())
+                .copied()
+                .collect::<Vec<u8>>(),
+        );
+        let bytes: Vec<u8> = (data.len() as u32)
+            .to_be_bytes()
+            .iter()
+            .chain(chunk_type_bytes.iter())
+            .chain(data.iter())
+            .chain(crc.to_be_bytes().iter())
+            .copied()
+            .collect();
+        Chunk::try_from(bytes.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn test_write_chunks_emits_signature_then_chunks_in_order() {
+        let first = test_chunk("RuSt", b"first");
+        let second = test_chunk("RuSt", b"second");
+
+        let mut first_bytes = Vec::new();
+        first.write_to(&mut first_bytes).unwrap();
+        let mut second_bytes = Vec::new();
+        second.write_to(&mut second_bytes).unwrap();
+
+        let mut out = Vec::new();
+        let mut writer = PngWriter::new(&mut out);
+        writer.write_chunks([&first, &second]).unwrap();
+
+        let mut expected = PNG_SIGNATURE.to_vec();
+        expected.extend_from_slice(&first_bytes);
+        expected.extend_from_slice(&second_bytes);
+        assert_eq!(out, expected);
+    }
+}